@@ -1,19 +1,58 @@
 // src/console.rs
 
 use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicBool, Ordering};
 use crate::sbi::console_putchar;
 
+// Set once a hart panics; from then on only the panic message is printed.
+static PANICKED: AtomicBool = AtomicBool::new(false);
+
 pub struct Stdout;
 
 impl Write for Stdout {
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        for c in s.chars() {
-            console_putchar(c as usize);
+        if PANICKED.load(Ordering::Acquire) {
+            park();
         }
+        putstr(s);
+        Ok(())
+    }
+}
+
+// Writes straight to the SBI console, ignoring PANICKED.
+struct PanicStdout;
+
+impl Write for PanicStdout {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        putstr(s);
         Ok(())
     }
 }
 
+fn putstr(s: &str) {
+    for c in s.chars() {
+        console_putchar(c as usize);
+    }
+}
+
+// Stops everyone else's output, then prints the panic message. Only the
+// first panicker prints; a later or nested panic parks silently. SBI
+// putchar is synchronous, so nothing is left buffered afterwards.
+pub fn panic_print(args: fmt::Arguments) {
+    if PANICKED.swap(true, Ordering::AcqRel) {
+        park();
+    }
+    let _ = PanicStdout.write_fmt(args);
+}
+
+// Halts this hart for good; used by harts that print after a panic.
+pub fn park() -> ! {
+    crate::riscv::intr_off();
+    loop {
+        crate::riscv::wfi();
+    }
+}
+
 #[macro_export]
 macro_rules! print {
     ($($arg:tt)*) => ({
@@ -0,0 +1,224 @@
+// src/fdt.rs
+
+// Minimal reader for the flattened device tree that firmware passes in a1.
+// Only what boot needs: walk nodes, read properties, decode `reg` and
+// find devices by their `compatible` string.
+// Malformed input ends the walk early rather than panicking.
+
+const FDT_MAGIC: u32 = 0xd00d_feed;
+
+const FDT_BEGIN_NODE: u32 = 1;
+const FDT_END_NODE: u32 = 2;
+const FDT_PROP: u32 = 3;
+const FDT_NOP: u32 = 4;
+const FDT_END: u32 = 9;
+
+const MAX_DEPTH: usize = 16;
+
+fn be32(data: &[u8], off: usize) -> Option<u32> {
+    let b = data.get(off..off.checked_add(4)?)?;
+    Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn align4(off: usize) -> usize {
+    (off + 3) & !3
+}
+
+// The bytes of the null-terminated string at off, without the terminator.
+fn cstr(data: &[u8], off: usize) -> Option<&[u8]> {
+    let rest = data.get(off..)?;
+    let len = rest.iter().position(|&c| c == 0)?;
+    Some(&rest[..len])
+}
+
+pub struct Fdt<'a> {
+    structs: &'a [u8],
+    strings: &'a [u8],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Device {
+    pub base: usize,
+    pub irq: Option<u32>,
+}
+
+pub struct Node<'a> {
+    pub name: &'a [u8],
+    props: &'a [u8],
+    strings: &'a [u8],
+    // cells used by this node's reg, set by its parent
+    addr_cells: u32,
+    size_cells: u32,
+}
+
+impl<'a> Fdt<'a> {
+    pub fn new(data: &'a [u8]) -> Option<Fdt<'a>> {
+        if be32(data, 0)? != FDT_MAGIC {
+            return None;
+        }
+        let total = be32(data, 4)? as usize;
+        let data = data.get(..total)?;
+        let off_struct = be32(data, 8)? as usize;
+        let off_strings = be32(data, 12)? as usize;
+        let size_strings = be32(data, 32)? as usize;
+        let size_struct = be32(data, 36)? as usize;
+        Some(Fdt {
+            structs: data.get(off_struct..off_struct.checked_add(size_struct)?)?,
+            strings: data.get(off_strings..off_strings.checked_add(size_strings)?)?,
+        })
+    }
+
+    /// Reads the blob firmware left at `addr`, trusting its header for the size.
+    ///
+    /// # Safety
+    /// `addr` must be 0 or point to memory that stays readable and unchanged
+    /// for the rest of the kernel's life.
+    pub unsafe fn from_addr(addr: usize) -> Option<Fdt<'static>> {
+        if addr == 0 || !addr.is_multiple_of(4) {
+            return None;
+        }
+        let header = core::slice::from_raw_parts(addr as *const u8, 8);
+        if be32(header, 0)? != FDT_MAGIC {
+            return None;
+        }
+        let total = be32(header, 4)? as usize;
+        Fdt::new(core::slice::from_raw_parts(addr as *const u8, total))
+    }
+
+    // Calls f on every node in document order.
+    pub fn for_each_node(&self, mut f: impl FnMut(&Node<'a>)) {
+        self.walk(&mut f);
+    }
+
+    fn walk(&self, f: &mut impl FnMut(&Node<'a>)) -> Option<()> {
+        let s = self.structs;
+        // cells[d] are the #address-cells/#size-cells that apply at depth d
+        let mut cells = [(2u32, 1u32); MAX_DEPTH + 1];
+        let mut depth = 0;
+        let mut off = 0;
+        loop {
+            match be32(s, off)? {
+                FDT_BEGIN_NODE => {
+                    let name = cstr(s, off + 4)?;
+                    off = align4(off + 4 + name.len() + 1);
+                    let props_start = off;
+                    loop {
+                        match be32(s, off)? {
+                            FDT_PROP => off = align4(off + 12 + be32(s, off + 4)? as usize),
+                            FDT_NOP => off += 4,
+                            _ => break,
+                        }
+                    }
+                    let (addr_cells, size_cells) = cells[depth];
+                    let node = Node {
+                        name,
+                        props: s.get(props_start..off)?,
+                        strings: self.strings,
+                        addr_cells,
+                        size_cells,
+                    };
+                    f(&node);
+                    if depth == MAX_DEPTH {
+                        return None;
+                    }
+                    depth += 1;
+                    cells[depth] = (
+                        node.prop_u32("#address-cells").unwrap_or(2),
+                        node.prop_u32("#size-cells").unwrap_or(1),
+                    );
+                }
+                FDT_END_NODE => {
+                    depth = depth.checked_sub(1)?;
+                    off += 4;
+                }
+                FDT_PROP => off = align4(off + 12 + be32(s, off + 4)? as usize),
+                FDT_NOP => off += 4,
+                FDT_END => return Some(()),
+                _ => return None,
+            }
+        }
+    }
+
+    // Base and size of the first memory node.
+    pub fn memory(&self) -> Option<(u64, u64)> {
+        let mut found = None;
+        self.for_each_node(|node| {
+            if found.is_none()
+                && (node.name == b"memory" || node.name.starts_with(b"memory@")
+                    || node.prop("device_type") == Some(b"memory\0"))
+            {
+                found = node.reg();
+            }
+        });
+        found
+    }
+
+    // The matching device with the lowest base address, so that e.g. the
+    // first virtio slot wins regardless of the order nodes appear in.
+    pub fn device(&self, compatible: &str) -> Option<Device> {
+        let mut found: Option<Device> = None;
+        self.for_each_node(|node| {
+            if !node.is_compatible(compatible) {
+                return;
+            }
+            if let Some((base, _)) = node.reg() {
+                let dev = Device { base: base as usize, irq: node.prop_u32("interrupts") };
+                if found.is_none_or(|f| dev.base < f.base) {
+                    found = Some(dev);
+                }
+            }
+        });
+        found
+    }
+}
+
+impl<'a> Node<'a> {
+    pub fn prop(&self, name: &str) -> Option<&'a [u8]> {
+        let p = self.props;
+        let mut off = 0;
+        while off < p.len() {
+            match be32(p, off)? {
+                FDT_PROP => {
+                    let len = be32(p, off + 4)? as usize;
+                    let nameoff = be32(p, off + 8)? as usize;
+                    if cstr(self.strings, nameoff)? == name.as_bytes() {
+                        return p.get(off + 12..off + 12 + len);
+                    }
+                    off = align4(off + 12 + len);
+                }
+                _ => off += 4,
+            }
+        }
+        None
+    }
+
+    // compatible is a list of null-terminated strings
+    pub fn is_compatible(&self, compatible: &str) -> bool {
+        self.prop("compatible").is_some_and(|list| {
+            list.split(|&c| c == 0).any(|s| s == compatible.as_bytes())
+        })
+    }
+
+    pub fn prop_u32(&self, name: &str) -> Option<u32> {
+        be32(self.prop(name)?, 0)
+    }
+
+    // First (address, size) pair of the reg property.
+    pub fn reg(&self) -> Option<(u64, u64)> {
+        let reg = self.prop("reg")?;
+        let addr = read_cells(reg, 0, self.addr_cells)?;
+        let size = read_cells(reg, self.addr_cells as usize * 4, self.size_cells)?;
+        Some((addr, size))
+    }
+}
+
+fn read_cells(data: &[u8], off: usize, n: u32) -> Option<u64> {
+    if n > 2 {
+        return None;
+    }
+    let mut v = 0u64;
+    for i in 0..n as usize {
+        v = (v << 32) | be32(data, off + i * 4)? as u64;
+    }
+    Some(v)
+}
@@ -0,0 +1,38 @@
+// src/kassert.rs
+
+// Invariant checks whose failure message carries the file and line of the
+// check itself, so a panic says where the kernel went wrong.
+
+#[macro_export]
+macro_rules! kassert {
+    ($cond:expr $(,)?) => ($crate::kassert!($cond, "{}", stringify!($cond)));
+    ($cond:expr, $($arg:tt)+) => ({
+        if !$cond {
+            panic!("kassert failed at {}:{}: {}", file!(), line!(), format_args!($($arg)+));
+        }
+    });
+}
+
+#[macro_export]
+macro_rules! kassert_eq {
+    ($left:expr, $right:expr $(,)?) => ({
+        match (&$left, &$right) {
+            (left, right) => {
+                if *left != *right {
+                    panic!("kassert_eq failed at {}:{}: {:?} != {:?}",
+                        file!(), line!(), left, right);
+                }
+            }
+        }
+    });
+    ($left:expr, $right:expr, $($arg:tt)+) => ({
+        match (&$left, &$right) {
+            (left, right) => {
+                if *left != *right {
+                    panic!("kassert_eq failed at {}:{}: {:?} != {:?}: {}",
+                        file!(), line!(), left, right, format_args!($($arg)+));
+                }
+            }
+        }
+    });
+}
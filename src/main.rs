@@ -5,7 +5,13 @@
 #![reexport_test_harness_main = "test_main"]
 
 pub mod console;
+pub mod fdt;
+pub mod kassert;
+pub mod memlayout;
+pub mod rand;
+pub mod riscv;
 pub mod sbi;
+pub mod string;
 pub mod test;
 
 use core::panic::PanicInfo;
@@ -14,8 +20,14 @@ use core::arch::global_asm;
 global_asm!(include_str!("arch/riscv/boot.S"));
 
 #[no_mangle]
-pub extern "C" fn rust_main() -> ! {
+pub extern "C" fn rust_main(_hartid: usize, dtb: usize) -> ! {
     println!("Hello RISCV!");
+    // SAFETY: dtb is the untouched a1 value OpenSBI passed to _start.
+    unsafe { memlayout::init(dtb) };
+    println!("[kernel] memory {:#x}..{:#x}", memlayout::KERNBASE, memlayout::phystop());
+    println!("[kernel] uart {:#x} irq {}, virtio {:#x} irq {}, plic {:#x}",
+        memlayout::uart0(), memlayout::uart0_irq(),
+        memlayout::virtio0(), memlayout::virtio0_irq(), memlayout::plic());
     
     #[cfg(test)]
     test_main();
@@ -27,8 +39,9 @@ pub extern "C" fn rust_main() -> ! {
 #[cfg(not(test))]
 #[panic_handler]
 fn panic(info: &PanicInfo) -> ! {
-    println!("\n\nPanic: {}", info);
-    loop {}
+    riscv::intr_off();
+    console::panic_print(format_args!("\n\nPanic: {}\n", info));
+    console::park()
 }
 
 #[cfg(test)]
@@ -58,4 +71,119 @@ fn test_println_many() {
     for i in 0..5 {
         println!("test line {}", i);
     }
-}
\ No newline at end of file
+}
+
+#[test_case]
+fn test_strcpy() {
+    let mut buf = [0xffu8; 8];
+    unsafe {
+        let dst = string::strcpy(buf.as_mut_ptr(), c"init".as_ptr().cast());
+        assert_eq!(dst, buf.as_mut_ptr());
+    }
+    assert_eq!(&buf, b"init\0\xff\xff\xff");
+    unsafe {
+        string::strcpy(buf.as_mut_ptr(), c"".as_ptr().cast());
+    }
+    assert_eq!(buf[0], 0);
+    assert_eq!(&buf[1..5], b"nit\0");
+}
+
+#[test_case]
+fn test_strchr() {
+    let s = c"kernel".as_ptr().cast::<u8>();
+    unsafe {
+        assert_eq!(string::strchr(s, b'r'), s.add(2));
+        assert_eq!(string::strchr(s, 0), s.add(6));
+        assert!(string::strchr(s, b'z').is_null());
+        assert!(string::strchr(c"".as_ptr().cast(), b'a').is_null());
+    }
+}
+
+#[test_case]
+fn test_memchr() {
+    let s = b"ab\0cd";
+    unsafe {
+        assert_eq!(string::memchr(s.as_ptr(), b'c', s.len()), s.as_ptr().add(3));
+        assert!(string::memchr(s.as_ptr(), b'd', 4).is_null());
+        assert!(string::memchr(s.as_ptr(), b'a', 0).is_null());
+    }
+}
+
+#[test_case]
+fn test_cstr_eq() {
+    unsafe {
+        assert!(string::cstr_eq(c"init".as_ptr().cast(), b"init"));
+        assert!(string::cstr_eq(c"init".as_ptr().cast(), b"init\0\0\0"));
+        assert!(!string::cstr_eq(c"init".as_ptr().cast(), b"ini"));
+        assert!(!string::cstr_eq(c"ini".as_ptr().cast(), b"init"));
+        assert!(string::cstr_eq(c"".as_ptr().cast(), b""));
+        assert!(!string::cstr_eq(c"".as_ptr().cast(), b"a"));
+    }
+}
+
+#[test_case]
+fn test_strncmp_user() {
+    let a = *b"abcdefghijklmn";
+    let b = *b"abcdefghijklmo";
+    assert!(string::strncmp_user(&a, &b) < 0);
+    assert!(string::strncmp_user(&b, &a) > 0);
+    assert_eq!(string::strncmp_user(&a, b"abcdefghijklmn\0"), 0);
+    // Only DIRSIZ bytes take part in the comparison.
+    assert_eq!(string::strncmp_user(&a, b"abcdefghijklmnXYZ"), 0);
+    assert_eq!(string::strncmp_user(b"ls\0\0\0", b"ls"), 0);
+}
+
+#[test_case]
+fn test_kassert_pass() {
+    let mut calls = 0;
+    let mut check = || {
+        calls += 1;
+        true
+    };
+    kassert!(check());
+    kassert!(calls == 1, "condition evaluated {} times", calls);
+    kassert_eq!(calls, 1);
+    kassert_eq!(calls * 2, 2, "doubled {}", calls);
+}
+
+#[test_case]
+fn test_cycle_counters() {
+    let c0 = riscv::r_cycle();
+    let i0 = riscv::r_instret();
+    let mut x = 0u64;
+    for i in 0..1000 {
+        x = core::hint::black_box(x + i);
+    }
+    assert!(riscv::r_cycle() > c0);
+    assert!(riscv::r_instret() > i0);
+}
+
+#[test_case]
+fn test_rand_fill() {
+    let mut a = [0u8; 32];
+    let mut b = [0u8; 32];
+    rand::fill(&mut a);
+    rand::fill(&mut b);
+    assert!(a.iter().any(|&x| x != 0));
+    assert_ne!(a, b);
+}
+
+#[test_case]
+fn test_fdt_memory() {
+    let fdt = fdt::Fdt::new(include_bytes!("arch/riscv/sample.dtb")).unwrap();
+    assert_eq!(fdt.memory(), Some((0x8000_0000, 0x1000_0000)));
+    assert_eq!(memlayout::phystop_from(&fdt), Some(0x9000_0000));
+    assert!(fdt::Fdt::new(&[0u8; 64]).is_none());
+}
+
+#[test_case]
+fn test_fdt_devices() {
+    use fdt::Device;
+    let fdt = fdt::Fdt::new(include_bytes!("arch/riscv/sample.dtb")).unwrap();
+    assert_eq!(fdt.device("ns16550a"), Some(Device { base: 0x1000_0000, irq: Some(10) }));
+    // the lowest-addressed virtio slot, though it comes last in the tree
+    assert_eq!(fdt.device("virtio,mmio"), Some(Device { base: 0x1000_1000, irq: Some(1) }));
+    assert_eq!(fdt.device("riscv,plic0"), Some(Device { base: 0x0c00_0000, irq: None }));
+    assert_eq!(fdt.device("sifive,plic-1.0.0").map(|d| d.base), Some(0x0c00_0000));
+    assert_eq!(fdt.device("virtio,net"), None);
+}
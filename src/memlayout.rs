@@ -0,0 +1,87 @@
+// src/memlayout.rs
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use crate::fdt::Fdt;
+
+// QEMU virt puts RAM here; OpenSBI sits at the bottom of it.
+pub const KERNBASE: usize = 0x8000_0000;
+
+// Used when there is no device tree or it has no memory node.
+pub const DEFAULT_PHYSTOP: usize = KERNBASE + 128 * 1024 * 1024;
+
+// QEMU virt device addresses, used when the device tree lacks a node.
+pub const DEFAULT_UART0: usize = 0x1000_0000;
+pub const DEFAULT_UART0_IRQ: usize = 10;
+pub const DEFAULT_VIRTIO0: usize = 0x1000_1000;
+pub const DEFAULT_VIRTIO0_IRQ: usize = 1;
+pub const DEFAULT_PLIC: usize = 0x0c00_0000;
+
+static PHYSTOP: AtomicUsize = AtomicUsize::new(DEFAULT_PHYSTOP);
+static UART0: AtomicUsize = AtomicUsize::new(DEFAULT_UART0);
+static UART0_IRQ: AtomicUsize = AtomicUsize::new(DEFAULT_UART0_IRQ);
+static VIRTIO0: AtomicUsize = AtomicUsize::new(DEFAULT_VIRTIO0);
+static VIRTIO0_IRQ: AtomicUsize = AtomicUsize::new(DEFAULT_VIRTIO0_IRQ);
+static PLIC: AtomicUsize = AtomicUsize::new(DEFAULT_PLIC);
+
+// End of usable physical RAM.
+pub fn phystop() -> usize {
+    PHYSTOP.load(Ordering::Relaxed)
+}
+
+pub fn uart0() -> usize {
+    UART0.load(Ordering::Relaxed)
+}
+
+pub fn uart0_irq() -> usize {
+    UART0_IRQ.load(Ordering::Relaxed)
+}
+
+pub fn virtio0() -> usize {
+    VIRTIO0.load(Ordering::Relaxed)
+}
+
+pub fn virtio0_irq() -> usize {
+    VIRTIO0_IRQ.load(Ordering::Relaxed)
+}
+
+pub fn plic() -> usize {
+    PLIC.load(Ordering::Relaxed)
+}
+
+/// Probes RAM and devices from the device tree firmware passed at boot;
+/// call before anything uses the values above.
+///
+/// # Safety
+/// `dtb` must be 0 or the device tree blob address firmware handed to the
+/// kernel in a1, left readable and unmodified for the kernel's lifetime.
+pub unsafe fn init(dtb: usize) {
+    // SAFETY: the caller guarantees dtb meets from_addr's contract.
+    let fdt = match unsafe { Fdt::from_addr(dtb) } {
+        Some(fdt) => fdt,
+        None => return,
+    };
+    if let Some(end) = phystop_from(&fdt) {
+        PHYSTOP.store(end, Ordering::Relaxed);
+    }
+    probe(&fdt, "ns16550a", &UART0, Some(&UART0_IRQ));
+    probe(&fdt, "virtio,mmio", &VIRTIO0, Some(&VIRTIO0_IRQ));
+    probe(&fdt, "riscv,plic0", &PLIC, None);
+}
+
+fn probe(fdt: &Fdt, compatible: &str, base: &AtomicUsize, irq: Option<&AtomicUsize>) {
+    if let Some(dev) = fdt.device(compatible) {
+        base.store(dev.base, Ordering::Relaxed);
+        if let (Some(irq), Some(n)) = (irq, dev.irq) {
+            irq.store(n as usize, Ordering::Relaxed);
+        }
+    }
+}
+
+pub fn phystop_from(fdt: &Fdt) -> Option<usize> {
+    let (base, size) = fdt.memory()?;
+    // Only RAM that starts where the kernel does is usable as-is.
+    if base as usize != KERNBASE || size == 0 {
+        return None;
+    }
+    (base as usize).checked_add(size as usize)
+}
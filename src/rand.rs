@@ -0,0 +1,44 @@
+// src/rand.rs
+
+use core::sync::atomic::{AtomicU64, Ordering};
+use crate::riscv::r_cycle;
+
+// Mix the cycle counter back into the state every this many draws.
+const RESEED_INTERVAL: u64 = 64;
+
+// xorshift64* state; updated with compare-exchange so no two successful
+// callers consume the same state. Reseeding can revisit an old state, so
+// outputs may still repeat.
+static STATE: AtomicU64 = AtomicU64::new(0);
+static DRAWS: AtomicU64 = AtomicU64::new(0);
+
+pub fn next_u64() -> u64 {
+    // The first draw lands on a multiple of RESEED_INTERVAL, which seeds us.
+    let reseed = DRAWS.fetch_add(1, Ordering::Relaxed).is_multiple_of(RESEED_INTERVAL);
+    let mut old = STATE.load(Ordering::Relaxed);
+    loop {
+        let mut x = old;
+        if reseed {
+            x ^= r_cycle();
+        }
+        if x == 0 {
+            // xorshift never leaves zero
+            x = 0x9e37_79b9_7f4a_7c15;
+        }
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        match STATE.compare_exchange_weak(old, x, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => return x.wrapping_mul(0x2545_f491_4f6c_dd1d),
+            Err(cur) => old = cur,
+        }
+    }
+}
+
+// Fill buf with pseudo-random bytes.
+pub fn fill(buf: &mut [u8]) {
+    for chunk in buf.chunks_mut(8) {
+        let r = next_u64().to_le_bytes();
+        chunk.copy_from_slice(&r[..chunk.len()]);
+    }
+}
@@ -0,0 +1,41 @@
+// src/riscv.rs
+
+use core::arch::asm;
+
+// Supervisor Interrupt Enable
+const SSTATUS_SIE: usize = 1 << 1;
+
+// disable device interrupts
+#[inline(always)]
+pub fn intr_off() {
+    unsafe {
+        asm!("csrc sstatus, {}", in(reg) SSTATUS_SIE);
+    }
+}
+
+#[inline(always)]
+pub fn wfi() {
+    unsafe {
+        asm!("wfi");
+    }
+}
+
+// cycles executed by this hart; OpenSBI enables S-mode access in mcounteren
+#[inline(always)]
+pub fn r_cycle() -> u64 {
+    let x: u64;
+    unsafe {
+        asm!("csrr {}, cycle", out(reg) x);
+    }
+    x
+}
+
+// instructions retired by this hart
+#[inline(always)]
+pub fn r_instret() -> u64 {
+    let x: u64;
+    unsafe {
+        asm!("csrr {}, instret", out(reg) x);
+    }
+    x
+}
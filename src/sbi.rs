@@ -12,6 +12,20 @@ const SBI_CONSOLE_PUTCHAR: usize = 1;
 // const SBI_REMOTE_SFENCE_VMA_ASID: usize = 7;
 const SBI_SHUTDOWN: usize = 8;
 
+// System Reset extension (SBI v0.3)
+const SBI_EXT_SRST: usize = 0x5352_5354;
+const SBI_SRST_TYPE_SHUTDOWN: usize = 0;
+const SBI_SRST_REASON_NONE: usize = 0;
+const SBI_SRST_REASON_FAILURE: usize = 1;
+
+// QEMU virt "sifive,test" finisher
+#[cfg(feature = "sifive-test")]
+const VIRT_TEST: usize = 0x10_0000;
+#[cfg(feature = "sifive-test")]
+const VIRT_TEST_PASS: u32 = 0x5555;
+#[cfg(feature = "sifive-test")]
+const VIRT_TEST_FAIL: u32 = 0x3333;
+
 #[inline(always)]
 fn sbi_call(which: usize, arg0: usize, arg1: usize, arg2: usize) -> usize {
     let mut ret;
@@ -27,11 +41,48 @@ fn sbi_call(which: usize, arg0: usize, arg1: usize, arg2: usize) -> usize {
     ret
 }
 
+// v0.2+ calling convention: extension in a7, function in a6, error in a0
+#[inline(always)]
+fn sbi_call_ext(eid: usize, fid: usize, arg0: usize, arg1: usize) -> isize {
+    let error: usize;
+    unsafe {
+        core::arch::asm!(
+            "ecall",
+            inlateout("x10") arg0 => error,
+            inlateout("x11") arg1 => _,
+            in("x16") fid,
+            in("x17") eid,
+        );
+    }
+    error as isize
+}
+
 pub fn console_putchar(c: usize) {
     sbi_call(SBI_CONSOLE_PUTCHAR, c, 0, 0);
 }
 
-pub fn shutdown() -> ! {
+pub fn shutdown(failure: bool) -> ! {
+    #[cfg(feature = "sifive-test")]
+    sifive_test_exit(failure);
+    #[cfg(not(feature = "sifive-test"))]
+    sbi_shutdown(failure);
+}
+
+fn sbi_shutdown(failure: bool) -> ! {
+    let reason = if failure { SBI_SRST_REASON_FAILURE } else { SBI_SRST_REASON_NONE };
+    sbi_call_ext(SBI_EXT_SRST, 0, SBI_SRST_TYPE_SHUTDOWN, reason);
+    // SRST is missing on older firmware; the legacy call has no exit status
     sbi_call(SBI_SHUTDOWN, 0, 0, 0);
     panic!("It should shutdown!");
+}
+
+#[cfg(feature = "sifive-test")]
+fn sifive_test_exit(failure: bool) -> ! {
+    // QEMU exits with the code in bits 16..31 on failure
+    let value = if failure { (1 << 16) | VIRT_TEST_FAIL } else { VIRT_TEST_PASS };
+    unsafe {
+        (VIRT_TEST as *mut u32).write_volatile(value);
+    }
+    // no finisher on this machine
+    sbi_shutdown(failure)
 }
\ No newline at end of file
@@ -0,0 +1,89 @@
+// src/string.rs
+
+// Longest name a directory entry can hold (the on-disk name field).
+pub const DIRSIZ: usize = 14;
+
+/// Copies the null-terminated string `src`, terminator included, to `dst`
+/// and returns `dst`.
+///
+/// # Safety
+/// `src` must be a readable null-terminated string, `dst` must be writable
+/// for its length plus one, and the two must not overlap.
+pub unsafe fn strcpy(dst: *mut u8, src: *const u8) -> *mut u8 {
+    let mut i = 0;
+    loop {
+        let c = *src.add(i);
+        *dst.add(i) = c;
+        if c == 0 {
+            return dst;
+        }
+        i += 1;
+    }
+}
+
+/// Returns a pointer to the first `c` in the null-terminated string `s`,
+/// or null if there is none. Searching for 0 finds the terminator.
+///
+/// # Safety
+/// `s` must point to a readable null-terminated string.
+pub unsafe fn strchr(s: *const u8, c: u8) -> *const u8 {
+    let mut p = s;
+    loop {
+        if *p == c {
+            return p;
+        }
+        if *p == 0 {
+            return core::ptr::null();
+        }
+        p = p.add(1);
+    }
+}
+
+/// Returns a pointer to the first `c` within the `n` bytes at `s`, or null.
+///
+/// # Safety
+/// `s` must be readable for `n` bytes.
+pub unsafe fn memchr(s: *const u8, c: u8, n: usize) -> *const u8 {
+    for i in 0..n {
+        if *s.add(i) == c {
+            return s.add(i);
+        }
+    }
+    core::ptr::null()
+}
+
+/// Compares the null-terminated string `a` with the name in `b`, looking at
+/// no more than `DIRSIZ` bytes. `b` ends at its first 0 or at its length.
+///
+/// # Safety
+/// `a` must be readable up to its terminator or `DIRSIZ` bytes.
+pub unsafe fn cstr_eq(a: *const u8, b: &[u8]) -> bool {
+    for i in 0..DIRSIZ {
+        let ca = *a.add(i);
+        let cb = b.get(i).copied().unwrap_or(0);
+        if ca != cb {
+            return false;
+        }
+        if ca == 0 {
+            return true;
+        }
+    }
+    true
+}
+
+// Compares two directory entry names like strncmp(a, b, DIRSIZ), but never
+// reads past the end of either slice: a name that fills its field has no
+// terminator, so the slice end counts as one.
+pub fn strncmp_user(a: &[u8], b: &[u8]) -> i32 {
+    for i in 0..DIRSIZ {
+        let ca = a.get(i).copied().unwrap_or(0);
+        let cb = b.get(i).copied().unwrap_or(0);
+        if ca != cb {
+            return ca as i32 - cb as i32;
+        }
+        if ca == 0 {
+            return 0;
+        }
+    }
+    0
+}
@@ -40,6 +40,5 @@ pub enum QemuExitCode {
 }
 
 pub fn exit_qemu(exit_code: QemuExitCode) -> ! {
-    let _ = exit_code;
-    crate::sbi::shutdown()
+    crate::sbi::shutdown(exit_code == QemuExitCode::Failed)
 }
\ No newline at end of file